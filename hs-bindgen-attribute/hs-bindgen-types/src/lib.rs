@@ -7,10 +7,7 @@ use thiserror::Error;
 
 /// Enumeration of all Haskell C-FFI safe types as the string representation of
 /// their token in Haskell.
-///
-/// FIXME: `Errno(c_int)` should be implemented as a Rust `enum` ...
-/// https://hackage.haskell.org/package/base/docs/Foreign-C-Error.html
-/// ... using `#[repr(i32)]` https://doc.rust-lang.org/nomicon/other-reprs.html
+#[derive(Clone)]
 #[non_exhaustive]
 pub enum HsType {
     /// `Int32`
@@ -37,6 +34,32 @@ pub enum HsType {
     CULLong,
     /// `Word8`
     CBool,
+    /// `CWchar`
+    CWchar,
+    /// `CPtrdiff`
+    CPtrdiff,
+    /// `CSize`
+    CSize,
+    /// `CIntPtr`
+    CIntPtr,
+    /// `CUIntPtr`
+    CUIntPtr,
+    /// `Int8`
+    Int8,
+    /// `Int16`
+    Int16,
+    /// `Int32`
+    Int32,
+    /// `Int64`
+    Int64,
+    /// `Word8`
+    Word8,
+    /// `Word16`
+    Word16,
+    /// `Word32`
+    Word32,
+    /// `Word64`
+    Word64,
     /// `Ptr CChar`
     CString,
     /// `Double`
@@ -45,10 +68,30 @@ pub enum HsType {
     CFloat,
     /// `()`
     Empty,
+    /// `()` — the element of an opaque `Ptr ()` handle, lowered to `c_void`.
+    Void,
+    /// A C `enum` modeled as a typed integer newtype (e.g. `Errno`), carrying
+    /// the Haskell type name and its integer representation (`CInt`, `CLong`,
+    /// …).
+    Enum { repr: Box<HsType>, name: String },
+    /// A `#[repr(C)]` struct passed by value, rendered Haskell-side as its type
+    /// constructor (`name`) and backed by a `Storable` record. `path` is the
+    /// Rust type path used for the FFI-safe by-value lowering — it may differ
+    /// from the Haskell name and may be qualified (`foo::Bar`). Carries the
+    /// fields in declaration order to let a companion generator compute offsets.
+    Struct {
+        path: String,
+        name: String,
+        fields: Vec<(String, HsType)>,
+    },
     /// `Ptr T`
     Ptr(Box<HsType>),
     /// `IO T`
     IO(Box<HsType>),
+    /// A fallible return modeled cxx-style: an out-parameter `*mut T` for the
+    /// success value plus an integer status word, surfaced Haskell-side as
+    /// `Either E T`.
+    Result { ok: Box<HsType>, err: Box<HsType> },
     /// FunPtr (S -> T)
     FunPtr(Vec<HsType>),
 }
@@ -74,9 +117,33 @@ impl std::fmt::Display for HsType {
                 HsType::CULLong => "CULLong".to_string(),
                 HsType::CULong => "CULong".to_string(),
                 HsType::CUShort => "CUShort".to_string(),
+                HsType::CWchar => "CWchar".to_string(),
+                HsType::CPtrdiff => "CPtrdiff".to_string(),
+                HsType::CSize => "CSize".to_string(),
+                HsType::CIntPtr => "CIntPtr".to_string(),
+                HsType::CUIntPtr => "CUIntPtr".to_string(),
+                HsType::Int8 => "Int8".to_string(),
+                HsType::Int16 => "Int16".to_string(),
+                HsType::Int32 => "Int32".to_string(),
+                HsType::Int64 => "Int64".to_string(),
+                HsType::Word8 => "Word8".to_string(),
+                HsType::Word16 => "Word16".to_string(),
+                HsType::Word32 => "Word32".to_string(),
+                HsType::Word64 => "Word64".to_string(),
                 HsType::Empty => "()".to_string(),
-                HsType::Ptr(x) => format!("Ptr ({x})"),
-                HsType::IO(x) => format!("IO ({x})"),
+                HsType::Void => "()".to_string(),
+                HsType::Enum { name, .. } => name.to_string(),
+                HsType::Struct { name, .. } => name.to_string(),
+                // An opaque handle is the idiomatic `Ptr ()`, not `Ptr (())`.
+                HsType::Ptr(x) => match **x {
+                    HsType::Void => "Ptr ()".to_string(),
+                    _ => format!("Ptr ({x})"),
+                },
+                HsType::IO(x) => match **x {
+                    HsType::Void => "IO ()".to_string(),
+                    _ => format!("IO ({x})"),
+                },
+                HsType::Result { ok, .. } => format!("Ptr ({ok}) -> IO CInt"),
                 HsType::FunPtr(types) => {
                     let args: Vec<String> = types.iter().map(|arg| format!("{arg}")).collect();
                     format!("FunPtr({})", args.join(" -> "))
@@ -160,8 +227,6 @@ impl std::str::FromStr for HsType {
                 .parse()?)
         } else if s.len() >= 2 && &s[..2] == "IO" {
             Ok(HsType::IO(Box::new(s[2..].parse()?)))
-        } else if s.len() >= 3 && &s[..3] == "Ptr" {
-            Ok(HsType::Ptr(Box::new(s[3..].parse()?)))
         } else if s.len() >= 6 && &s[..6] == "FunPtr" {
             let mut s = s[6..].trim();
 
@@ -180,6 +245,26 @@ impl std::str::FromStr for HsType {
             }
 
             Ok(HsType::FunPtr(types))
+        } else if s.len() >= 3 && &s[..3] == "Ptr" {
+            let inner = s[3..].trim();
+            // A bare `Ptr` or `Ptr ()` is the opaque `Ptr ()` handle idiom.
+            if inner.is_empty() || inner == "()" {
+                Ok(HsType::Ptr(Box::new(HsType::Void)))
+            } else {
+                Ok(HsType::Ptr(Box::new(inner.parse()?)))
+            }
+        } else if s.len() >= 4 && &s[..4] == "Enum" {
+            let s = s[4..]
+                .trim()
+                .strip_prefix('(')
+                .ok_or(Error::UnmatchedParenthesis)?
+                .strip_suffix(')')
+                .ok_or(Error::UnmatchedParenthesis)?;
+            let (name, repr) = s.split_once(',').ok_or(Error::UnmatchedParenthesis)?;
+            Ok(HsType::Enum {
+                repr: Box::new(repr.parse()?),
+                name: name.trim().to_string(),
+            })
         } else {
             match s {
                 "CBool" => Ok(HsType::CBool),
@@ -197,6 +282,19 @@ impl std::str::FromStr for HsType {
                 "CULLong" => Ok(HsType::CULLong),
                 "CULong" => Ok(HsType::CULong),
                 "CUShort" => Ok(HsType::CUShort),
+                "CWchar" => Ok(HsType::CWchar),
+                "CPtrdiff" => Ok(HsType::CPtrdiff),
+                "CSize" => Ok(HsType::CSize),
+                "CIntPtr" => Ok(HsType::CIntPtr),
+                "CUIntPtr" => Ok(HsType::CUIntPtr),
+                "Int8" => Ok(HsType::Int8),
+                "Int16" => Ok(HsType::Int16),
+                "Int32" => Ok(HsType::Int32),
+                "Int64" => Ok(HsType::Int64),
+                "Word8" => Ok(HsType::Word8),
+                "Word16" => Ok(HsType::Word16),
+                "Word32" => Ok(HsType::Word32),
+                "Word64" => Ok(HsType::Word64),
                 ty => Err(Error::UnsupportedHsType(ty.to_string())),
             }
         }
@@ -229,12 +327,47 @@ impl HsType {
             HsType::CULLong => quote! { core::ffi::c_ulonglong },
             HsType::CULong => quote! { core::ffi::c_ulong },
             HsType::CUShort => quote! { core::ffi::c_ushort },
+            // `wchar_t` is 2 bytes on Windows, 4 bytes (`c_int`-sized) elsewhere.
+            HsType::CWchar => {
+                cfg_if! {
+                    if #[cfg(windows)] {
+                        quote! { i16 }
+                    } else {
+                        quote! { core::ffi::c_int }
+                    }
+                }
+            }
+            HsType::CPtrdiff => quote! { isize },
+            HsType::CSize => quote! { usize },
+            HsType::CIntPtr => quote! { isize },
+            HsType::CUIntPtr => quote! { usize },
+            HsType::Int8 => quote! { i8 },
+            HsType::Int16 => quote! { i16 },
+            HsType::Int32 => quote! { i32 },
+            HsType::Int64 => quote! { i64 },
+            HsType::Word8 => quote! { u8 },
+            HsType::Word16 => quote! { u16 },
+            HsType::Word32 => quote! { u32 },
+            HsType::Word64 => quote! { u64 },
             HsType::Empty => quote! { () },
+            HsType::Void => quote! { core::ffi::c_void },
+            // A C `enum` is FFI-safe as its `#[repr(…)]` integer primitive.
+            HsType::Enum { repr, .. } => repr.quote(),
+            // A `#[repr(C)]` struct is FFI-safe by value as its own Rust type
+            // path (which may be qualified, so parse rather than `Ident::new`).
+            HsType::Struct { path, .. } => path
+                .parse()
+                .expect("`HsType::Struct` path should be a valid Rust type path"),
             HsType::Ptr(x) => {
                 let ty = x.quote();
                 quote! { *const #ty }
             }
             HsType::IO(x) => x.quote(),
+            // `Result<T, E>` becomes an out-parameter plus a status word.
+            HsType::Result { ok, .. } => {
+                let ty = ok.quote();
+                quote!(unsafe extern "C" fn(*mut #ty) -> core::ffi::c_int)
+            }
             HsType::FunPtr(types) => {
                 let ret = types.last().unwrap().quote();
                 let args: Vec<_> = types[..types.len() - 1]
@@ -245,6 +378,154 @@ impl HsType {
             }
         }
     }
+
+    /// Haskell `foreign import ccall "wrapper"` declarations for every
+    /// `FunPtr` reachable from this type, deduplicated by signature.
+    ///
+    /// Producing a `FunPtr` from a Haskell closure requires such a declaration;
+    /// this emits the `mk…` factory for each callback signature the type tree
+    /// mentions, e.g. `mkCIntCInt :: (CInt -> CInt) -> IO (FunPtr (CInt -> CInt))`.
+    pub fn wrapper_imports(&self) -> Vec<String> {
+        let mut seen = Vec::new();
+        let mut out = Vec::new();
+        self.collect_wrappers(&mut seen, &mut out);
+        out
+    }
+
+    /// Haskell glue wrapping a fallible C import: allocate the out-parameter,
+    /// call the status-returning import `c_<name>`, and reconstruct
+    /// `Either E T` from the status word. Returns `None` for non-`Result` types.
+    pub fn either_wrapper(&self, name: &str) -> Option<String> {
+        let HsType::Result { ok, err } = self else {
+            return None;
+        };
+        Some(format!(
+            "{name} :: IO (Either {err} {ok})\n\
+             {name} = alloca $ \\out -> do\n\
+             \x20 status <- c_{name} out\n\
+             \x20 if status == 0\n\
+             \x20   then Right <$> peek out\n\
+             \x20   else return (Left (toEnum (fromIntegral status)))"
+        ))
+    }
+
+    fn collect_wrappers(&self, seen: &mut Vec<String>, out: &mut Vec<String>) {
+        match self {
+            HsType::FunPtr(types) => {
+                let sig = types
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                if !seen.contains(&sig) {
+                    let name: String = sig.chars().filter(|c| c.is_alphanumeric()).collect();
+                    out.push(format!(
+                        "foreign import ccall \"wrapper\" mk{name} :: ({sig}) -> IO (FunPtr ({sig}))"
+                    ));
+                    seen.push(sig);
+                }
+                for t in types {
+                    t.collect_wrappers(seen, out);
+                }
+            }
+            HsType::Ptr(x) | HsType::IO(x) => x.collect_wrappers(seen, out),
+            HsType::Result { ok, err } => {
+                ok.collect_wrappers(seen, out);
+                err.collect_wrappers(seen, out);
+            }
+            HsType::Enum { repr, .. } => repr.collect_wrappers(seen, out),
+            HsType::Struct { fields, .. } => {
+                for (_, t) in fields {
+                    t.collect_wrappers(seen, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Size in bytes of the C representation of this type, assuming the LP64
+    /// data model C headers are typically compiled under.
+    fn size(&self) -> usize {
+        match self {
+            HsType::CBool
+            | HsType::CChar
+            | HsType::CSChar
+            | HsType::CUChar
+            | HsType::Int8
+            | HsType::Word8 => 1,
+            HsType::CShort | HsType::CUShort | HsType::Int16 | HsType::Word16 => 2,
+            // `wchar_t` is 2 bytes on Windows, 4 bytes elsewhere.
+            HsType::CWchar => {
+                cfg_if! {
+                    if #[cfg(windows)] { 2 } else { 4 }
+                }
+            }
+            HsType::CInt | HsType::CUInt | HsType::CFloat | HsType::Int32 | HsType::Word32 => 4,
+            HsType::CLong
+            | HsType::CULong
+            | HsType::CLLong
+            | HsType::CULLong
+            | HsType::CDouble
+            | HsType::CString
+            | HsType::CPtrdiff
+            | HsType::CSize
+            | HsType::CIntPtr
+            | HsType::CUIntPtr
+            | HsType::Int64
+            | HsType::Word64
+            | HsType::Ptr(_)
+            | HsType::FunPtr(_) => 8,
+            HsType::Empty | HsType::Void => 0,
+            HsType::Enum { repr, .. } => repr.size(),
+            HsType::IO(x) => x.size(),
+            HsType::Result { .. } => 0,
+            HsType::Struct { fields, .. } => {
+                let (size, align) = Self::record_layout(fields);
+                // Tail-pad the whole record up to its alignment, as C does.
+                size.next_multiple_of(align.max(1))
+            }
+        }
+    }
+
+    /// Alignment in bytes of the C representation of this type.
+    fn align(&self) -> usize {
+        match self {
+            HsType::Struct { fields, .. } => Self::record_layout(fields).1,
+            HsType::Empty => 1,
+            other => other.size().max(1),
+        }
+    }
+
+    /// Lay out the fields of a `#[repr(C)]` record, returning the running size
+    /// before tail-padding and the record's overall alignment.
+    fn record_layout(fields: &[(String, HsType)]) -> (usize, usize) {
+        let mut offset: usize = 0;
+        let mut align = 1;
+        for (_, ty) in fields {
+            let a = ty.align();
+            align = align.max(a);
+            offset = offset.next_multiple_of(a);
+            offset += ty.size();
+        }
+        (offset, align)
+    }
+
+    /// Field offsets (in bytes) of a `HsType::Struct`, in declaration order, as
+    /// needed to emit a Haskell `Storable` instance. Returns an empty vector
+    /// for non-struct types.
+    pub fn storable_offsets(&self) -> Vec<(String, HsType, usize)> {
+        let HsType::Struct { fields, .. } = self else {
+            return Vec::new();
+        };
+        let mut offset: usize = 0;
+        let mut out = Vec::with_capacity(fields.len());
+        for (name, ty) in fields {
+            offset = offset.next_multiple_of(ty.align());
+            out.push((name.clone(), ty.clone(), offset));
+            offset += ty.size();
+        }
+        out
+    }
 }
 
 /// Turn a given Rust type into his `HsType` target.
@@ -267,6 +548,43 @@ macro_rules! repr_hs {
 }
 pub(crate) use repr_hs;
 
+/// Map a Rust `#[repr(i32)]`-style `enum` to an `HsType::Enum`, i.e. a typed
+/// Haskell integer newtype. The `ReprHs` implementation is what a derive on a
+/// C-like enum expands to.
+macro_rules! repr_hs_enum {
+    ($($ty:ty => $name:literal : $repr:ident,)*) => {$(
+        impl ReprHs for $ty {
+            fn into() -> HsType {
+                HsType::Enum {
+                    repr: Box::new(HsType::$repr),
+                    name: $name.to_string(),
+                }
+            }
+        }
+    )*};
+}
+pub(crate) use repr_hs_enum;
+
+/// Map a Rust `#[repr(C)]` struct to an `HsType::Struct`, recursing through
+/// `ReprHs` for each field so offsets stay in sync with the field types. This
+/// is what a derive on a shared struct expands to.
+macro_rules! repr_hs_struct {
+    ($($ty:ty => $name:literal { $($field:ident : $fty:ty),* $(,)? },)*) => {$(
+        impl ReprHs for $ty {
+            fn into() -> HsType {
+                HsType::Struct {
+                    path: stringify!($ty).to_string(),
+                    name: $name.to_string(),
+                    fields: vec![
+                        $((stringify!($field).to_string(), <$fty as ReprHs>::into())),*
+                    ],
+                }
+            }
+        }
+    )*};
+}
+pub(crate) use repr_hs_struct;
+
 repr_hs! {
     c_char   => CChar,
     c_double => CDouble,
@@ -276,9 +594,20 @@ repr_hs! {
     c_uchar  => CUChar,
     c_uint   => CUInt,
     c_ushort => CUShort,
+    c_void   => Void,
     ()       => Empty,
 }
 
+/// Marker element type for opaque C handles, so `*const Opaque` / `*mut Opaque`
+/// map to the Haskell `Ptr ()` idiom just like `*const c_void` does.
+pub struct Opaque;
+
+impl ReprHs for Opaque {
+    fn into() -> HsType {
+        HsType::Void
+    }
+}
+
 cfg_if! {
     if #[cfg(all(target_pointer_width = "64", not(windows)))] {
         repr_hs! {
@@ -293,6 +622,50 @@ cfg_if! {
     }
 }
 
+repr_hs! {
+    usize => CSize,
+    isize => CPtrdiff,
+}
+
+// KNOWN LIMITATION (exact-width `ReprHs`): the request asked for `ReprHs` on the
+// bare `i8..i64`/`u8..u64` primitives, but those types *are* the `core::ffi::c_*`
+// aliases (`c_int == i32`, `c_short == i16`, …), so a blanket impl would collide
+// with the ones above. We deliberately let the C-named mapping win for the bare
+// primitives — a plain `u32`/`i16` field resolves to `CUInt`/`CShort`, matching
+// how C headers spell those types. Reaching the exact-width `Int{N}`/`Word{N}`
+// variants is therefore opt-in, via the marker types below.
+
+/// Exact-width marker types (`Data.Int`/`Data.Word`). A binding must use these
+/// to request the `Int{N}`/`Word{N}` Haskell variants: the bare Rust primitives
+/// are the `core::ffi::c_*` aliases and so resolve to the C-named `ReprHs` impls
+/// (`u32` → `CUInt`, not `Word32`).
+pub struct I8;
+/// See [`I8`].
+pub struct I16;
+/// See [`I8`].
+pub struct I32;
+/// See [`I8`].
+pub struct I64;
+/// See [`I8`].
+pub struct W8;
+/// See [`I8`].
+pub struct W16;
+/// See [`I8`].
+pub struct W32;
+/// See [`I8`].
+pub struct W64;
+
+repr_hs! {
+    I8  => Int8,
+    I16 => Int16,
+    I32 => Int32,
+    I64 => Int64,
+    W8  => Word8,
+    W16 => Word16,
+    W32 => Word32,
+    W64 => Word64,
+}
+
 impl<T> ReprHs for *const T
 where
     T: ReprHs,
@@ -311,6 +684,19 @@ where
     }
 }
 
+impl<T, E> ReprHs for Result<T, E>
+where
+    T: ReprHs,
+    E: ReprHs,
+{
+    fn into() -> HsType {
+        HsType::Result {
+            ok: Box::new(T::into()),
+            err: Box::new(E::into()),
+        }
+    }
+}
+
 /* ********** Vector & Slices ********** */
 
 impl<T> ReprHs for Vec<T>
@@ -341,3 +727,66 @@ repr_hs! {
     String  => CString,
     &str    => CString,
 }
+
+/* ********** Enums ********** */
+
+/// C `errno`, modeled as the typed Haskell `Errno` integer newtype rather than
+/// a raw `CInt` — the worked example of the C-enum mapping.
+#[repr(i32)]
+pub enum Errno {
+    Ok = 0,
+}
+
+repr_hs_enum! {
+    Errno => "Errno" : CInt,
+}
+
+/* ********** Structs ********** */
+
+/// A `#[repr(C)]` point, the worked example of the shared-struct-by-value
+/// mapping: passed across the boundary by value rather than through a `Ptr`.
+#[repr(C)]
+pub struct Point {
+    pub x: c_double,
+    pub y: c_double,
+}
+
+repr_hs_struct! {
+    Point => "Point" { x: c_double, y: c_double },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_maps_to_typed_integer_newtype() {
+        let ty = <Errno as ReprHs>::into();
+        // Display is the Haskell newtype name; quote() the FFI-safe repr.
+        assert_eq!(ty.to_string(), "Errno");
+        assert_eq!(ty.quote().to_string(), quote! { core::ffi::c_int }.to_string());
+        // And the `Enum(Name, CInt)` round-trips through FromStr.
+        assert_eq!("Enum(Errno, CInt)".parse::<HsType>().unwrap().to_string(), "Errno");
+    }
+
+    #[test]
+    fn struct_maps_to_storable_record() {
+        let ty = <Point as ReprHs>::into();
+        // Display renders the Haskell constructor, `quote()` the Rust path.
+        assert_eq!(ty.to_string(), "Point");
+        assert_eq!(ty.quote().to_string(), quote! { Point }.to_string());
+        // Fields keep declaration order with C-computed offsets.
+        let offsets = ty.storable_offsets();
+        assert_eq!(offsets.len(), 2);
+        assert_eq!(offsets[0].0, "x");
+        assert_eq!(offsets[0].2, 0);
+        assert_eq!(offsets[1].0, "y");
+        assert_eq!(offsets[1].2, 8);
+    }
+
+    #[test]
+    fn fixed_width_markers_reach_exact_width_variants() {
+        assert_eq!(<I32 as ReprHs>::into().to_string(), "Int32");
+        assert_eq!(<W64 as ReprHs>::into().to_string(), "Word64");
+    }
+}